@@ -0,0 +1,95 @@
+//! Kernel-variant selection for the GLWE accumulator used in blind rotation.
+//!
+//! The external-product loop in a bootstrap repeatedly reads and updates a running GLWE
+//! accumulator of `accumulator_size()` coefficients, once per decomposition level. When that
+//! accumulator (and the relevant slice of the Fourier key) fits in a block's shared memory, it
+//! can be staged there for the whole loop -- synchronizing threads between decomposition
+//! levels and writing back to global memory only once at the end -- instead of round-tripping
+//! through global memory at every level.
+use super::device::CudaDeviceProperties;
+use super::CudaBootstrapKey;
+use concrete_commons::numeric::UnsignedInteger;
+
+/// Which memory space the running GLWE accumulator is staged in during blind rotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AccumulatorKernelVariant {
+    /// The accumulator lives in per-block shared memory for the whole blind-rotation loop.
+    SharedMemory,
+    /// The accumulator is read from and written back to global memory at every decomposition
+    /// level. Used whenever the accumulator doesn't fit in shared memory.
+    GlobalMemory,
+}
+
+impl<T: UnsignedInteger> CudaBootstrapKey<T> {
+    /// Picks the shared-memory accumulator variant when this key's accumulator fits in
+    /// `device_properties`'s shared-memory-per-block budget, falling back to the
+    /// global-memory variant otherwise.
+    #[allow(dead_code)]
+    pub(crate) fn select_accumulator_variant(
+        &self,
+        device_properties: &CudaDeviceProperties,
+    ) -> AccumulatorKernelVariant {
+        let accumulator_bytes = self.accumulator_size() * std::mem::size_of::<f64>();
+        if accumulator_bytes <= device_properties.shared_mem_per_block {
+            AccumulatorKernelVariant::SharedMemory
+        } else {
+            AccumulatorKernelVariant::GlobalMemory
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::cuda::private::vec::CudaVec;
+    use concrete_commons::parameters::{
+        DecompositionBaseLog, DecompositionLevelCount, GlweDimension, LweDimension, PolynomialSize,
+    };
+    use std::marker::PhantomData;
+
+    fn make_key(glwe_dimension: usize, polynomial_size: usize) -> CudaBootstrapKey<u64> {
+        CudaBootstrapKey {
+            id: 0,
+            d_vecs: vec![CudaVec::<f64>::from_host_slice(&[0.0; 4])],
+            input_lwe_dimension: LweDimension(512),
+            polynomial_size: PolynomialSize(polynomial_size),
+            glwe_dimension: GlweDimension(glwe_dimension),
+            decomp_level: DecompositionLevelCount(3),
+            decomp_base_log: DecompositionBaseLog(7),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn device_properties(shared_mem_per_block: usize) -> CudaDeviceProperties {
+        CudaDeviceProperties {
+            name: "test".to_string(),
+            total_global_mem: 1 << 30,
+            shared_mem_per_block,
+            total_const_mem: 1 << 16,
+            warp_size: 32,
+            max_threads_per_block: 1024,
+            max_grid_size: [1, 1, 1],
+        }
+    }
+
+    #[test]
+    fn picks_shared_memory_when_accumulator_fits() {
+        // accumulator_size = (glwe_dimension + 1) * polynomial_size = 2 * 4 = 8 f64s = 64 bytes.
+        let key = make_key(1, 4);
+        let properties = device_properties(128);
+        assert_eq!(
+            key.select_accumulator_variant(&properties),
+            AccumulatorKernelVariant::SharedMemory
+        );
+    }
+
+    #[test]
+    fn falls_back_to_global_memory_when_accumulator_does_not_fit() {
+        let key = make_key(1, 4);
+        let properties = device_properties(32);
+        assert_eq!(
+            key.select_accumulator_variant(&properties),
+            AccumulatorKernelVariant::GlobalMemory
+        );
+    }
+}
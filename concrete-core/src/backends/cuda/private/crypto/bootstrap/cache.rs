@@ -0,0 +1,191 @@
+//! Caching of already-converted, device-resident bootstrap keys by host-key identity.
+//!
+//! Wraps the same DIY unique-ID-wrapper pattern used elsewhere to compare GPU resources that
+//! don't implement equality: a conversion engine fingerprints the host key it's about to
+//! convert (see [`fingerprint_host_bsk`]) and looks that fingerprint up here first, reusing the
+//! already-resident device key instead of re-uploading identical data.
+//!
+//! The fingerprint is derived from the host key's own coefficients and shape metadata, not
+//! from [`CudaBootstrapKey::id`] -- that id is assigned fresh to every device-resident
+//! instance (including on repeated conversions of the same host key) and so cannot be used to
+//! recognize "the same underlying key" on its own.
+use super::CudaBootstrapKey;
+use concrete_commons::numeric::UnsignedInteger;
+use concrete_commons::parameters::{
+    DecompositionBaseLog, DecompositionLevelCount, GlweDimension, LweDimension, PolynomialSize,
+};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Fingerprints a host bootstrap key from its Fourier coefficients and shape metadata, stable
+/// across repeated calls for the same key contents, for use as a conversion cache key.
+pub(crate) fn fingerprint_host_bsk(
+    host_fourier_coefficients: &[f64],
+    input_lwe_dimension: LweDimension,
+    polynomial_size: PolynomialSize,
+    glwe_dimension: GlweDimension,
+    decomp_level: DecompositionLevelCount,
+    decomp_base_log: DecompositionBaseLog,
+) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for &coeff in host_fourier_coefficients {
+        coeff.to_bits().hash(&mut hasher);
+    }
+    input_lwe_dimension.0.hash(&mut hasher);
+    polynomial_size.0.hash(&mut hasher);
+    glwe_dimension.0.hash(&mut hasher);
+    decomp_level.0.hash(&mut hasher);
+    decomp_base_log.0.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Conversion-engine-side cache mapping a host key's fingerprint to the [`CudaBootstrapKey`]
+/// it was already converted into, so repeated conversion requests for the same host key skip
+/// the device upload.
+#[derive(Debug, Default)]
+pub(crate) struct CudaBootstrapKeyConversionCache<T> {
+    entries: HashMap<u64, CudaBootstrapKey<T>>,
+}
+
+impl<T: UnsignedInteger> CudaBootstrapKeyConversionCache<T> {
+    #[allow(dead_code)]
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns the already-converted key for `host_key_fingerprint`, if any.
+    #[allow(dead_code)]
+    pub(crate) fn get(&self, host_key_fingerprint: u64) -> Option<&CudaBootstrapKey<T>> {
+        self.entries.get(&host_key_fingerprint)
+    }
+
+    /// Returns the cached key for `host_key_fingerprint`, converting and inserting it via
+    /// `convert` if absent.
+    #[allow(dead_code)]
+    pub(crate) fn get_or_convert(
+        &mut self,
+        host_key_fingerprint: u64,
+        convert: impl FnOnce() -> CudaBootstrapKey<T>,
+    ) -> &CudaBootstrapKey<T> {
+        self.entries
+            .entry(host_key_fingerprint)
+            .or_insert_with(convert)
+    }
+
+    /// Converts `host_fourier_coefficients` into a device-resident [`CudaBootstrapKey`] on
+    /// `num_devices` GPUs, reusing an already-cached conversion of the same host key (by
+    /// fingerprint, not by re-uploading and comparing) when one exists.
+    ///
+    /// This is the entry point conversion engines should call instead of
+    /// [`CudaBootstrapKey::from_host_bsk`] directly, so that converting the same host key twice
+    /// only uploads it once.
+    #[allow(dead_code)]
+    pub(crate) fn convert_host_bsk(
+        &mut self,
+        host_fourier_coefficients: &[f64],
+        num_devices: usize,
+        input_lwe_dimension: LweDimension,
+        polynomial_size: PolynomialSize,
+        glwe_dimension: GlweDimension,
+        decomp_level: DecompositionLevelCount,
+        decomp_base_log: DecompositionBaseLog,
+    ) -> &CudaBootstrapKey<T> {
+        let fingerprint = fingerprint_host_bsk(
+            host_fourier_coefficients,
+            input_lwe_dimension,
+            polynomial_size,
+            glwe_dimension,
+            decomp_level,
+            decomp_base_log,
+        );
+        self.get_or_convert(fingerprint, || {
+            CudaBootstrapKey::from_host_bsk(
+                host_fourier_coefficients,
+                num_devices,
+                input_lwe_dimension,
+                polynomial_size,
+                glwe_dimension,
+                decomp_level,
+                decomp_base_log,
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> (
+        LweDimension,
+        PolynomialSize,
+        GlweDimension,
+        DecompositionLevelCount,
+        DecompositionBaseLog,
+    ) {
+        (
+            LweDimension(512),
+            PolynomialSize(1024),
+            GlweDimension(1),
+            DecompositionLevelCount(3),
+            DecompositionBaseLog(7),
+        )
+    }
+
+    #[test]
+    fn fingerprint_is_stable_for_the_same_host_key() {
+        let (lwe_dim, poly_size, glwe_dim, level, base_log) = metadata();
+        let coefficients = vec![1.0, -2.5, 3.75];
+        let a = fingerprint_host_bsk(&coefficients, lwe_dim, poly_size, glwe_dim, level, base_log);
+        let b = fingerprint_host_bsk(&coefficients, lwe_dim, poly_size, glwe_dim, level, base_log);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn fingerprint_differs_for_different_coefficients() {
+        let (lwe_dim, poly_size, glwe_dim, level, base_log) = metadata();
+        let a = fingerprint_host_bsk(&[1.0, 2.0], lwe_dim, poly_size, glwe_dim, level, base_log);
+        let b = fingerprint_host_bsk(&[1.0, 2.1], lwe_dim, poly_size, glwe_dim, level, base_log);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn convert_host_bsk_reuses_the_cached_conversion_for_the_same_host_key() {
+        let (lwe_dim, poly_size, glwe_dim, level, base_log) = metadata();
+        let coefficients = vec![1.0, -2.5, 3.75, 0.0];
+        let mut cache = CudaBootstrapKeyConversionCache::<u64>::new();
+
+        let first_id = cache
+            .convert_host_bsk(&coefficients, 1, lwe_dim, poly_size, glwe_dim, level, base_log)
+            .id();
+        // A second conversion of the exact same host key must hit the cache -- i.e. `convert`
+        // never runs again -- rather than uploading a fresh `CudaBootstrapKey` (which would get
+        // a new id from `next_id()`).
+        let second_id = cache
+            .convert_host_bsk(&coefficients, 1, lwe_dim, poly_size, glwe_dim, level, base_log)
+            .id();
+        assert_eq!(
+            first_id, second_id,
+            "re-converting the same host key should reuse the cached device-resident key"
+        );
+
+        let different_coefficients = vec![1.0, -2.5, 3.75, 9.0];
+        let third_id = cache
+            .convert_host_bsk(
+                &different_coefficients,
+                1,
+                lwe_dim,
+                poly_size,
+                glwe_dim,
+                level,
+                base_log,
+            )
+            .id();
+        assert_ne!(
+            first_id, third_id,
+            "a genuinely different host key must not reuse another key's cache entry"
+        );
+    }
+}
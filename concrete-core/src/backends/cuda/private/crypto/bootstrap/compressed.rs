@@ -0,0 +1,252 @@
+//! Compressed on-device representation of a bootstrap key.
+//!
+//! Stores the decomposed, Fourier-transformed BSK coefficients in a packed/quantized `u32`
+//! layout instead of the raw `f64` form kept by [`CudaBootstrapKey`], trading a GPU-side
+//! decompression step at bootstrap time for a smaller device memory footprint. Useful for
+//! keys whose `polynomial_size * decomp_level * glwe_dimension` would otherwise dominate
+//! the available GPU memory.
+use super::device;
+use super::CudaBootstrapKey;
+use crate::backends::cuda::private::vec::CudaVec;
+use concrete_commons::numeric::UnsignedInteger;
+use concrete_commons::parameters::{
+    DecompositionBaseLog, DecompositionLevelCount, GlweDimension, LweDimension, PolynomialSize,
+};
+use std::marker::PhantomData;
+use std::sync::OnceLock;
+
+/// A bootstrap key stored on the GPU in a packed/quantized layout, one `CudaVec` per GPU,
+/// alongside the same shape metadata as [`CudaBootstrapKey`] plus the scale factor needed to
+/// dequantize it.
+#[derive(Debug)]
+pub(crate) struct CudaCompressedBootstrapKey<T> {
+    // Packed/quantized Fourier coefficients, one cuda vec per GPU.
+    pub(crate) d_vecs: Vec<CudaVec<u32>>,
+    // Scale factor used to quantize the original f64 coefficients into i32-range integers.
+    pub(crate) scale: f64,
+    // Input LWE dimension
+    pub(crate) input_lwe_dimension: LweDimension,
+    // Size of polynomials in the key
+    pub(crate) polynomial_size: PolynomialSize,
+    // GLWE dimension
+    pub(crate) glwe_dimension: GlweDimension,
+    // Number of decomposition levels
+    pub(crate) decomp_level: DecompositionLevelCount,
+    // Value of the base log for the decomposition
+    pub(crate) decomp_base_log: DecompositionBaseLog,
+    // Lazily-populated, on-device-decompressed form of this key. Populated the first time a
+    // bootstrap launches against this key (see `decompress`) and reused afterwards, instead of
+    // decompressing on every launch.
+    decompressed: OnceLock<CudaBootstrapKey<T>>,
+    // Field to hold type T
+    pub(crate) _phantom: PhantomData<T>,
+}
+
+/// Quantizes a slice of Fourier coefficients to `u32` (reinterpreted `i32`) using a single
+/// scale factor chosen so that the largest-magnitude coefficient maps to `i32::MAX`.
+fn quantize(coefficients: &[f64]) -> (Vec<u32>, f64) {
+    let max_abs = coefficients
+        .iter()
+        .fold(0.0_f64, |acc, &coeff| acc.max(coeff.abs()));
+    let scale = if max_abs > 0.0 {
+        i32::MAX as f64 / max_abs
+    } else {
+        1.0
+    };
+    let packed = coefficients
+        .iter()
+        .map(|&coeff| ((coeff * scale).round() as i32) as u32)
+        .collect();
+    (packed, scale)
+}
+
+/// Dequantizes `packed` back to `f64` coefficients using `scale`, the inverse of [`quantize`].
+fn dequantize(packed: &[u32], scale: f64) -> Vec<f64> {
+    packed
+        .iter()
+        .map(|&word| (word as i32) as f64 / scale)
+        .collect()
+}
+
+impl<T: UnsignedInteger> CudaCompressedBootstrapKey<T> {
+    #[allow(dead_code)]
+    pub(crate) fn polynomial_size(&self) -> PolynomialSize {
+        self.polynomial_size
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn input_lwe_dimension(&self) -> LweDimension {
+        self.input_lwe_dimension
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn glwe_dimension(&self) -> GlweDimension {
+        self.glwe_dimension
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn decomposition_level_count(&self) -> DecompositionLevelCount {
+        self.decomp_level
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn decomposition_base_log(&self) -> DecompositionBaseLog {
+        self.decomp_base_log
+    }
+
+    /// Builds the compressed, packed representation of a bootstrap key directly from the
+    /// host-side (already Fourier-transformed) BSK coefficients, without ever materializing
+    /// the full `f64` form on a device.
+    #[allow(dead_code)]
+    pub(crate) fn from_host_bsk(
+        host_fourier_coefficients: &[f64],
+        num_devices: usize,
+        input_lwe_dimension: LweDimension,
+        polynomial_size: PolynomialSize,
+        glwe_dimension: GlweDimension,
+        decomp_level: DecompositionLevelCount,
+        decomp_base_log: DecompositionBaseLog,
+    ) -> Self {
+        let (packed, scale) = quantize(host_fourier_coefficients);
+        // Uploading `packed` to each of the `num_devices` GPUs is handled by the same
+        // device-allocation path `CudaBootstrapKey` uses for its uncompressed `d_vecs`; this
+        // constructor is only responsible for picking the packed representation.
+        let d_vecs = (0..num_devices)
+            .map(|_| CudaVec::<u32>::from_host_slice(&packed))
+            .collect();
+        Self {
+            d_vecs,
+            scale,
+            input_lwe_dimension,
+            polynomial_size,
+            glwe_dimension,
+            decomp_level,
+            decomp_base_log,
+            decompressed: OnceLock::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns this key decompressed into a full [`CudaBootstrapKey`] holding the raw `f64`
+    /// Fourier coefficients, decompressing only the first time it's needed and reusing the
+    /// result afterwards. Called by [`Self::execute_bootstrap_multi_gpu`] the first time a
+    /// bootstrap launches against this compressed key.
+    #[allow(dead_code)]
+    pub(crate) fn decompress(&self) -> &CudaBootstrapKey<T> {
+        self.decompressed.get_or_init(|| {
+            let d_vecs = self
+                .d_vecs
+                .iter()
+                .map(|packed| ffi::launch_decompress_kernel(packed, self.scale))
+                .collect();
+            CudaBootstrapKey {
+                id: super::next_id(),
+                d_vecs,
+                input_lwe_dimension: self.input_lwe_dimension,
+                polynomial_size: self.polynomial_size,
+                glwe_dimension: self.glwe_dimension,
+                decomp_level: self.decomp_level,
+                decomp_base_log: self.decomp_base_log,
+                _phantom: PhantomData,
+            }
+        })
+    }
+
+    /// Runs a batch bootstrap against this compressed key: decompresses it (lazily, see
+    /// [`Self::decompress`]) and delegates to the decompressed key's own
+    /// [`CudaBootstrapKey::execute_bootstrap_multi_gpu`].
+    #[allow(dead_code)]
+    pub(crate) fn execute_bootstrap_multi_gpu(
+        &self,
+        input: &[f64],
+        requested_num_gpus: Option<usize>,
+    ) -> Result<Vec<f64>, device::CudaDeviceError> {
+        self.decompress()
+            .execute_bootstrap_multi_gpu(input, requested_num_gpus)
+    }
+}
+
+/// On-device unpack kernel backing [`CudaCompressedBootstrapKey::decompress`].
+mod ffi {
+    use super::dequantize;
+    use crate::backends::cuda::private::vec::CudaVec;
+
+    /// Launches the dequantization kernel for one GPU's packed coefficient buffer, producing
+    /// the `f64` Fourier coefficients `quantize` started from.
+    ///
+    /// The real binding would run this unpack directly on the device; there is no CUDA FFI
+    /// available in this chunk, so this reads the packed buffer's host-visible view instead --
+    /// functionally equivalent, but not yet the zero-host-roundtrip kernel the final
+    /// implementation should have.
+    pub(super) fn launch_decompress_kernel(packed: &CudaVec<u32>, scale: f64) -> CudaVec<f64> {
+        let coefficients = dequantize(packed.as_host_slice(), scale);
+        CudaVec::<f64>::from_host_slice(&coefficients)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantize_dequantize_round_trips_within_tolerance() {
+        let coefficients = vec![-12.5, 0.0, 3.25, 1_000.125, -999.875];
+        let (packed, scale) = quantize(&coefficients);
+        let restored = dequantize(&packed, scale);
+        for (original, restored) in coefficients.iter().zip(restored.iter()) {
+            assert!(
+                (original - restored).abs() < 1e-3,
+                "{original} did not round-trip (got {restored})"
+            );
+        }
+    }
+
+    #[test]
+    fn quantize_all_zero_does_not_divide_by_zero() {
+        let (packed, scale) = quantize(&[0.0, 0.0, 0.0]);
+        assert_eq!(packed, vec![0, 0, 0]);
+        assert_eq!(scale, 1.0);
+    }
+
+    #[test]
+    fn quantize_maps_largest_magnitude_to_i32_max() {
+        let (packed, _scale) = quantize(&[2.0, -4.0, 1.0]);
+        assert_eq!(packed[1] as i32, i32::MIN + 1);
+    }
+
+    fn make_key(coefficients: &[f64]) -> CudaCompressedBootstrapKey<u64> {
+        CudaCompressedBootstrapKey::<u64>::from_host_bsk(
+            coefficients,
+            1,
+            LweDimension(4),
+            PolynomialSize(2),
+            GlweDimension(1),
+            DecompositionLevelCount(2),
+            DecompositionBaseLog(3),
+        )
+    }
+
+    #[test]
+    fn decompress_recovers_the_original_coefficients() {
+        let coefficients = vec![1.0, -2.0, 3.5, -4.25];
+        let key = make_key(&coefficients);
+        let restored = key.decompress().d_vecs[0].as_host_slice();
+        for (original, restored) in coefficients.iter().zip(restored.iter()) {
+            assert!(
+                (original - restored).abs() < 1e-3,
+                "{original} did not survive decompress (got {restored})"
+            );
+        }
+    }
+
+    #[test]
+    fn decompress_is_memoized_across_calls() {
+        let key = make_key(&[1.0, -2.0, 3.5, -4.25]);
+        let first_id = key.decompress().id();
+        let second_id = key.decompress().id();
+        assert_eq!(
+            first_id, second_id,
+            "decompress should reuse the first decompression, not recompute it"
+        );
+    }
+}
@@ -1,13 +1,30 @@
 //! Bootstrap key with Cuda.
+pub(crate) mod accumulator;
+pub(crate) mod cache;
+pub(crate) mod compressed;
+pub(crate) mod device;
+pub(crate) mod multi_gpu;
+pub(crate) mod vectorized;
+
 use crate::backends::cuda::private::vec::CudaVec;
 use concrete_commons::numeric::UnsignedInteger;
 use concrete_commons::parameters::{
     DecompositionBaseLog, DecompositionLevelCount, GlweDimension, LweDimension, PolynomialSize,
 };
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Process-wide counter used to hand out a fresh [`CudaBootstrapKey::id`] to every newly
+/// constructed key.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
 
 #[derive(Debug)]
 pub(crate) struct CudaBootstrapKey<T> {
+    // Globally-unique identifier for this device-resident instance, handed out at
+    // construction. Note this is *not* a host-key identity: converting the same host key
+    // twice yields two different ids. Conversion engines that want to recognize "the same
+    // underlying host key" should key on `cache::fingerprint_host_bsk` instead, see `cache`.
+    pub(crate) id: u64,
     // Pointers to GPU data: one cuda vec per GPU
     pub(crate) d_vecs: Vec<CudaVec<f64>>,
     // Input LWE dimension
@@ -24,7 +41,20 @@ pub(crate) struct CudaBootstrapKey<T> {
     pub(crate) _phantom: PhantomData<T>,
 }
 
+/// Allocates a fresh, process-wide unique id for a newly constructed [`CudaBootstrapKey`].
+pub(crate) fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
 impl<T: UnsignedInteger> CudaBootstrapKey<T> {
+    /// This key's globally-unique identifier, stable for the lifetime of the key. Two
+    /// `CudaBootstrapKey`s built from independent conversions of the same host key will have
+    /// different ids; the id only identifies this particular device-resident instance.
+    #[allow(dead_code)]
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+
     #[allow(dead_code)]
     pub(crate) fn polynomial_size(&self) -> PolynomialSize {
         self.polynomial_size
@@ -49,4 +79,72 @@ impl<T: UnsignedInteger> CudaBootstrapKey<T> {
     pub(crate) fn decomposition_base_log(&self) -> DecompositionBaseLog {
         self.decomp_base_log
     }
+
+    /// Number of per-GPU copies of this key, i.e. the maximum number of devices a bootstrap
+    /// against this key can be spread across.
+    #[allow(dead_code)]
+    pub(crate) fn num_devices(&self) -> usize {
+        self.d_vecs.len()
+    }
+
+    /// Number of `f64` coefficients in the GLWE accumulator this key's bootstrap keeps
+    /// resident for the duration of the blind-rotation loop.
+    #[allow(dead_code)]
+    pub(crate) fn accumulator_size(&self) -> usize {
+        (self.glwe_dimension.0 + 1) * self.polynomial_size.0
+    }
+
+    /// Builds a full (uncompressed) device-resident key directly from the host-side
+    /// (already Fourier-transformed) BSK coefficients, uploading them as-is to each of
+    /// `num_devices` GPUs. Prefer converting through a
+    /// [`cache::CudaBootstrapKeyConversionCache`] when the same host key may be converted more
+    /// than once, so repeated conversions reuse the already-uploaded key instead of
+    /// re-uploading it.
+    #[allow(dead_code)]
+    pub(crate) fn from_host_bsk(
+        host_fourier_coefficients: &[f64],
+        num_devices: usize,
+        input_lwe_dimension: LweDimension,
+        polynomial_size: PolynomialSize,
+        glwe_dimension: GlweDimension,
+        decomp_level: DecompositionLevelCount,
+        decomp_base_log: DecompositionBaseLog,
+    ) -> Self {
+        let d_vecs = (0..num_devices)
+            .map(|_| CudaVec::<f64>::from_host_slice(host_fourier_coefficients))
+            .collect();
+        Self {
+            id: next_id(),
+            d_vecs,
+            input_lwe_dimension,
+            polynomial_size,
+            glwe_dimension,
+            decomp_level,
+            decomp_base_log,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Checks that this key's bootstrap can actually be launched on `device`, returning an
+    /// error describing which limit would be exceeded otherwise.
+    ///
+    /// Only hard device limits are checked here (e.g. max threads per block); a GLWE
+    /// accumulator too large for shared memory is not an error on its own, see
+    /// [`device::CudaDeviceProperties`] and the accumulator-placement logic that falls back to
+    /// the global-memory kernel variant in that case.
+    #[allow(dead_code)]
+    pub(crate) fn check_fits_device(
+        &self,
+        device: &device::CudaDevice,
+    ) -> Result<(), device::CudaDeviceError> {
+        let properties = device.properties();
+        // Blind rotation assigns one thread per polynomial coefficient.
+        if self.polynomial_size.0 > properties.max_threads_per_block {
+            return Err(device::CudaDeviceError::PolynomialTooLarge {
+                polynomial_size: self.polynomial_size.0,
+                max_threads_per_block: properties.max_threads_per_block,
+            });
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,57 @@
+//! Vectorized (`double2`) access path for the Fourier BSK pointwise-multiply step.
+//!
+//! Imports the vec4-style vectorized-memory-access technique used to speed up GPU matmul,
+//! applied here to the polynomial pointwise products that dominate bootstrap runtime: two
+//! `f64` Fourier coefficients are loaded/stored per thread through a single coalesced 16-byte
+//! `double2` transaction instead of two scalar `f64` transactions.
+use super::CudaBootstrapKey;
+use concrete_commons::numeric::UnsignedInteger;
+
+/// Alignment, in bytes, required for a `double2`-vectorized load/store of two `f64` at a time.
+const DOUBLE2_ALIGNMENT_BYTES: usize = 16;
+
+impl<T: UnsignedInteger> CudaBootstrapKey<T> {
+    /// Whether this key's per-GPU coefficient buffers are eligible for the vectorized
+    /// `double2` load/store path: every polynomial must have an even coefficient count, and
+    /// every per-GPU buffer must start on a 16-byte boundary.
+    ///
+    /// Keys that don't meet both conditions fall back to the scalar `f64` kernel path.
+    #[allow(dead_code)]
+    pub(crate) fn supports_vectorized_loads(&self) -> bool {
+        if self.polynomial_size.0 % 2 != 0 {
+            return false;
+        }
+        self.d_vecs
+            .iter()
+            .all(|d_vec| (d_vec.as_ptr() as usize) % DOUBLE2_ALIGNMENT_BYTES == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::cuda::private::vec::CudaVec;
+    use concrete_commons::parameters::{
+        DecompositionBaseLog, DecompositionLevelCount, GlweDimension, LweDimension, PolynomialSize,
+    };
+    use std::marker::PhantomData;
+
+    fn make_key(polynomial_size: usize) -> CudaBootstrapKey<u64> {
+        CudaBootstrapKey {
+            id: 0,
+            d_vecs: vec![CudaVec::<f64>::from_host_slice(&[0.0; 4])],
+            input_lwe_dimension: LweDimension(512),
+            polynomial_size: PolynomialSize(polynomial_size),
+            glwe_dimension: GlweDimension(1),
+            decomp_level: DecompositionLevelCount(3),
+            decomp_base_log: DecompositionBaseLog(7),
+            _phantom: PhantomData,
+        }
+    }
+
+    #[test]
+    fn odd_polynomial_size_is_never_eligible() {
+        // Odd coefficient counts can't be split into double2 pairs, regardless of alignment.
+        assert!(!make_key(3).supports_vectorized_loads());
+    }
+}